@@ -0,0 +1,191 @@
+use anyhow::Context;
+use serde::Deserialize;
+use std::collections::HashMap;
+use twilight_model::id::{
+    marker::{ChannelMarker, GuildMarker, RoleMarker},
+    Id,
+};
+
+/// Bot configuration, loaded once at startup from `config.json` (if present)
+/// and overridden by `DISCORD_DOCKER_STATUS__*` environment variables. This
+/// lets the same binary serve multiple guilds/deployments without
+/// recompiling.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Settings {
+    pub guild_id: Id<GuildMarker>,
+    pub category_id: Id<ChannelMarker>,
+    #[serde(default = "default_poll_interval_secs")]
+    pub poll_interval_secs: u64,
+    #[serde(default = "default_log_tail_lines")]
+    pub log_tail_lines: u64,
+    #[serde(default = "default_embed_color")]
+    pub embed_color: u32,
+    #[serde(default = "default_cache_path")]
+    pub cache_path: String,
+    #[serde(default)]
+    pub containers: ContainerFilter,
+    /// Roles allowed to use the destructive restart/stop/start buttons.
+    /// Anyone else gets an ephemeral "not allowed" response. Empty means
+    /// nobody is allowed to use them.
+    #[serde(default)]
+    pub allowed_roles: Vec<Id<RoleMarker>>,
+}
+
+/// Name-based allow/deny list and Docker label selector for which containers
+/// get a channel at all.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ContainerFilter {
+    #[serde(default)]
+    pub allow: Vec<String>,
+    #[serde(default)]
+    pub deny: Vec<String>,
+    /// A Docker label filter passed straight through to
+    /// `ListContainersOptions`, e.g. `"discord-status.enable=true"`.
+    #[serde(default)]
+    pub label_selector: Option<String>,
+}
+
+impl ContainerFilter {
+    /// Whether a container's labels satisfy `label_selector`, for callers
+    /// that already have a container's labels on hand (e.g. an
+    /// inspect-based lookup) instead of being able to delegate to Docker's
+    /// own server-side list filter like `docker::containers` does. Supports
+    /// both forms Docker's own `label` filter does: `key=value` and bare
+    /// `key` (existence only, any value).
+    pub fn label_allowed(&self, labels: &HashMap<String, String>) -> bool {
+        let Some(selector) = self.label_selector.as_deref() else {
+            return true;
+        };
+
+        match selector.split_once('=') {
+            Some((key, value)) => labels.get(key).is_some_and(|v| v == value),
+            None => labels.contains_key(selector),
+        }
+    }
+}
+
+fn default_poll_interval_secs() -> u64 {
+    300
+}
+
+fn default_log_tail_lines() -> u64 {
+    40
+}
+
+fn default_embed_color() -> u32 {
+    0x3772FF
+}
+
+fn default_cache_path() -> String {
+    "cache.db".to_string()
+}
+
+impl Settings {
+    pub fn load() -> anyhow::Result<Self> {
+        let config = config::Config::builder()
+            .add_source(config::File::with_name("config").required(false))
+            .add_source(
+                config::Environment::with_prefix("DISCORD_DOCKER_STATUS").separator("__"),
+            )
+            .build()
+            .context("Failed to load configuration")?;
+
+        config
+            .try_deserialize()
+            .context("Failed to parse configuration")
+    }
+
+    /// Whether a container should get a channel at all, per the configured
+    /// allow/deny list. Deny takes precedence; an empty allow list means
+    /// everything not denied is allowed.
+    pub fn container_allowed(&self, name: &str) -> bool {
+        if self.containers.deny.iter().any(|denied| denied == name) {
+            return false;
+        }
+
+        self.containers.allow.is_empty() || self.containers.allow.iter().any(|a| a == name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings_with(allow: Vec<&str>, deny: Vec<&str>) -> Settings {
+        Settings {
+            guild_id: Id::new(1),
+            category_id: Id::new(1),
+            poll_interval_secs: default_poll_interval_secs(),
+            log_tail_lines: default_log_tail_lines(),
+            embed_color: default_embed_color(),
+            cache_path: default_cache_path(),
+            containers: ContainerFilter {
+                allow: allow.into_iter().map(str::to_string).collect(),
+                deny: deny.into_iter().map(str::to_string).collect(),
+                label_selector: None,
+            },
+            allowed_roles: vec![],
+        }
+    }
+
+    #[test]
+    fn empty_allow_list_allows_everything_not_denied() {
+        let settings = settings_with(vec![], vec!["web"]);
+
+        assert!(settings.container_allowed("db"));
+        assert!(!settings.container_allowed("web"));
+    }
+
+    #[test]
+    fn allow_list_restricts_to_named_containers() {
+        let settings = settings_with(vec!["db"], vec![]);
+
+        assert!(settings.container_allowed("db"));
+        assert!(!settings.container_allowed("web"));
+    }
+
+    #[test]
+    fn deny_takes_precedence_over_allow() {
+        let settings = settings_with(vec!["db"], vec!["db"]);
+
+        assert!(!settings.container_allowed("db"));
+    }
+
+    #[test]
+    fn no_selector_allows_everything() {
+        let filter = ContainerFilter::default();
+
+        assert!(filter.label_allowed(&HashMap::new()));
+    }
+
+    #[test]
+    fn key_value_selector_requires_matching_value() {
+        let filter = ContainerFilter {
+            label_selector: Some("discord-status.enable=true".to_string()),
+            ..Default::default()
+        };
+
+        let mut labels = HashMap::new();
+        labels.insert("discord-status.enable".to_string(), "true".to_string());
+        assert!(filter.label_allowed(&labels));
+
+        labels.insert("discord-status.enable".to_string(), "false".to_string());
+        assert!(!filter.label_allowed(&labels));
+
+        assert!(!filter.label_allowed(&HashMap::new()));
+    }
+
+    #[test]
+    fn existence_only_selector_ignores_value() {
+        let filter = ContainerFilter {
+            label_selector: Some("discord-status.enable".to_string()),
+            ..Default::default()
+        };
+
+        let mut labels = HashMap::new();
+        labels.insert("discord-status.enable".to_string(), "anything".to_string());
+        assert!(filter.label_allowed(&labels));
+
+        assert!(!filter.label_allowed(&HashMap::new()));
+    }
+}
@@ -1,17 +1,152 @@
 use anyhow::Context;
 use bollard::container::LogOutput;
+use chrono::Utc;
 use itertools::Itertools;
+use log::warn;
+use tokio::sync::mpsc;
+use twilight_gateway::{Event, Intents, Shard, ShardId};
 use twilight_model::{
+    application::interaction::{Interaction, InteractionData},
     channel::message::{
-        embed::{EmbedAuthor, EmbedFooter},
-        Embed,
+        component::{ActionRow, Button, ButtonStyle},
+        embed::{EmbedAuthor, EmbedField, EmbedFooter},
+        Component, Embed, MessageFlags,
     },
+    http::interaction::{InteractionResponse, InteractionResponseData, InteractionResponseType},
+    id::{marker::RoleMarker, Id},
     util::Timestamp,
 };
 
-use crate::docker::Container;
+use crate::docker::{Container, ContainerHealth, ContainerState};
 
-pub fn embed_container(container: &Container, logs: &[LogOutput]) -> anyhow::Result<Embed> {
+/// Discord's standard green/yellow/red status colors.
+const COLOR_GREEN: u32 = 0x57F287;
+const COLOR_YELLOW: u32 = 0xFEE75C;
+const COLOR_RED: u32 = 0xED4245;
+
+/// Spawns a long-lived task that connects to the Discord gateway and
+/// forwards message component interactions, so `run` can react to button
+/// presses instead of only rendering read-only embeds.
+pub fn watch_interactions(token: String) -> mpsc::Receiver<Interaction> {
+    let (tx, rx) = mpsc::channel(64);
+
+    tokio::spawn(async move {
+        let mut shard = Shard::new(ShardId::ONE, token, Intents::GUILDS);
+
+        loop {
+            let event = match shard.next_event().await {
+                Ok(event) => event,
+                Err(e) => {
+                    warn!("Error receiving gateway event: {:?}", e);
+                    continue;
+                }
+            };
+
+            if let Event::InteractionCreate(interaction) = event {
+                if tx.send(interaction.0).await.is_err() {
+                    break;
+                }
+            }
+        }
+    });
+
+    rx
+}
+
+/// Builds the "Restart"/"Stop"/"Start"/"Refresh logs" action row attached to
+/// a container's message. Custom IDs are `<action>:<container id>` so the
+/// interaction handler can route the press without any extra state.
+pub fn container_components(container: &Container) -> Vec<Component> {
+    let button = |action: &str, label: &str, style: ButtonStyle| {
+        Component::Button(Button {
+            custom_id: Some(format!("{}:{}", action, container.id)),
+            disabled: false,
+            emoji: None,
+            label: Some(label.to_string()),
+            style,
+            url: None,
+            sku_id: None,
+        })
+    };
+
+    vec![Component::ActionRow(ActionRow {
+        components: vec![
+            button("restart", "Restart", ButtonStyle::Primary),
+            button("stop", "Stop", ButtonStyle::Danger),
+            button("start", "Start", ButtonStyle::Success),
+            button("refresh", "Refresh logs", ButtonStyle::Secondary),
+        ],
+    })]
+}
+
+/// Parses the `<action>:<container id>` custom ID off a message component
+/// interaction.
+pub fn interaction_action<'a>(interaction: &'a Interaction) -> Option<(&'a str, &'a str)> {
+    let Some(InteractionData::MessageComponent(data)) = interaction.data.as_ref() else {
+        return None;
+    };
+
+    data.custom_id.split_once(':')
+}
+
+/// Whether the interacting member has one of the given role IDs, used to
+/// gate the destructive buttons (restart/stop/start) behind a configurable
+/// allow list.
+pub fn member_has_role(interaction: &Interaction, allowed_roles: &[Id<RoleMarker>]) -> bool {
+    interaction
+        .member
+        .as_ref()
+        .map(|member| member.roles.iter().any(|role| allowed_roles.contains(role)))
+        .unwrap_or(false)
+}
+
+/// An ephemeral acknowledgement so the operator who pressed a button gets
+/// feedback without spamming the channel.
+pub fn ephemeral_response(content: &str) -> InteractionResponse {
+    InteractionResponse {
+        kind: InteractionResponseType::ChannelMessageWithSource,
+        data: Some(InteractionResponseData {
+            allowed_mentions: None,
+            attachments: None,
+            choices: None,
+            components: None,
+            content: Some(content.to_string()),
+            custom_id: None,
+            embeds: None,
+            flags: Some(MessageFlags::EPHEMERAL),
+            title: None,
+            tts: None,
+        }),
+    }
+}
+
+/// An ephemeral "thinking" ack, sent immediately so Discord's 3-second
+/// interaction deadline doesn't expire while a slow docker action (e.g.
+/// `stop` with its ~10s grace period) is still running. The final result is
+/// filled in later via `update_response`.
+pub fn deferred_response() -> InteractionResponse {
+    InteractionResponse {
+        kind: InteractionResponseType::DeferredChannelMessageWithSource,
+        data: Some(InteractionResponseData {
+            allowed_mentions: None,
+            attachments: None,
+            choices: None,
+            components: None,
+            content: None,
+            custom_id: None,
+            embeds: None,
+            flags: Some(MessageFlags::EPHEMERAL),
+            title: None,
+            tts: None,
+        }),
+    }
+}
+
+pub fn embed_container(
+    container: &Container,
+    logs: &[LogOutput],
+    embed_color: u32,
+) -> anyhow::Result<Embed> {
     let logs = logs
         .iter()
         .map(|l| {
@@ -41,14 +176,35 @@ pub fn embed_container(container: &Container, logs: &[LogOutput]) -> anyhow::Res
             proxy_icon_url: None,
             url: None,
         }),
-        color: Some(0x3772FF),
-        description: Some(format!(
-            "Image `{}`\nRunning `{}`:\n```{}```",
-            container.image,
-            container.command,
-            &logs[(logs.len() as i64 - 3900).max(0) as usize..],
-        )),
-        fields: vec![],
+        color: Some(state_color(container, embed_color)),
+        description: Some(format!("```{}```", tail_chars(&logs, 3900))),
+        fields: vec![
+            EmbedField {
+                inline: true,
+                name: "Image".to_string(),
+                value: field_value(&container.image),
+            },
+            EmbedField {
+                inline: true,
+                name: "Command".to_string(),
+                value: field_value(&container.command),
+            },
+            EmbedField {
+                inline: true,
+                name: "State".to_string(),
+                value: field_value(&state_field(container)),
+            },
+            EmbedField {
+                inline: true,
+                name: "Health".to_string(),
+                value: health_field(container),
+            },
+            EmbedField {
+                inline: true,
+                name: "Uptime".to_string(),
+                value: uptime_field(container),
+            },
+        ],
         footer: Some(EmbedFooter {
             icon_url: None,
             proxy_icon_url: None,
@@ -69,3 +225,208 @@ pub fn embed_container(container: &Container, logs: &[LogOutput]) -> anyhow::Res
 
     Ok(embed)
 }
+
+/// Returns the last `n` characters of `s`, on a char boundary (unlike a raw
+/// byte-offset slice, which panics if the cut lands mid-character).
+fn tail_chars(s: &str, n: usize) -> &str {
+    if n == 0 {
+        return "";
+    }
+
+    match s.char_indices().rev().nth(n.saturating_sub(1)) {
+        Some((i, _)) => &s[i..],
+        None => s,
+    }
+}
+
+/// Discord rejects embed field values outside 1-1024 chars; an
+/// entrypoint-only container's command (or any other field sourced from
+/// Docker) can be empty, and logs/commands can run long, so clamp into that
+/// range instead of letting `update_message` 400.
+fn field_value(value: &str) -> String {
+    if value.is_empty() {
+        return "-".to_string();
+    }
+
+    if value.chars().count() > 1024 {
+        value.chars().take(1024).collect()
+    } else {
+        value.to_string()
+    }
+}
+
+/// Green for a healthy/running container, yellow for something mid-transition
+/// (starting, paused, restarting), red for exited/unhealthy. Falls back to
+/// the configured default color for anything else.
+fn state_color(container: &Container, default_color: u32) -> u32 {
+    match (container.state, container.health) {
+        (_, Some(ContainerHealth::Unhealthy)) => COLOR_RED,
+        (ContainerState::Exited, _) => COLOR_RED,
+        (_, Some(ContainerHealth::Starting)) => COLOR_YELLOW,
+        (ContainerState::Paused | ContainerState::Restarting, _) => COLOR_YELLOW,
+        (ContainerState::Running, _) => COLOR_GREEN,
+        (ContainerState::Other, _) => default_color,
+    }
+}
+
+fn state_field(container: &Container) -> String {
+    match container.state {
+        ContainerState::Running => "Running".to_string(),
+        ContainerState::Exited => match container.exit_code {
+            Some(code) => format!("Exited ({})", code),
+            None => "Exited".to_string(),
+        },
+        ContainerState::Paused => "Paused".to_string(),
+        ContainerState::Restarting => "Restarting".to_string(),
+        ContainerState::Other => container.status.clone(),
+    }
+}
+
+fn health_field(container: &Container) -> String {
+    match container.health {
+        Some(ContainerHealth::Healthy) => "Healthy".to_string(),
+        Some(ContainerHealth::Unhealthy) => "Unhealthy".to_string(),
+        Some(ContainerHealth::Starting) => "Starting".to_string(),
+        None => "-".to_string(),
+    }
+}
+
+fn uptime_field(container: &Container) -> String {
+    let (ContainerState::Running, Some(started_at)) = (container.state, container.started_at)
+    else {
+        return "-".to_string();
+    };
+
+    let elapsed = Utc::now() - started_at;
+    let total_seconds = elapsed.num_seconds().max(0);
+    let days = total_seconds / 86400;
+    let hours = (total_seconds % 86400) / 3600;
+    let minutes = (total_seconds % 3600) / 60;
+
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn test_container() -> Container {
+        Container {
+            id: "abc123".to_string(),
+            name: "web".to_string(),
+            image: "web:latest".to_string(),
+            command: "serve".to_string(),
+            status: "Up".to_string(),
+            state: ContainerState::Running,
+            health: None,
+            started_at: None,
+            exit_code: None,
+            display_name: "web".to_string(),
+            log_tail_override: None,
+            hidden: false,
+            labels: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn state_color_prefers_unhealthy_over_running() {
+        let container = Container {
+            health: Some(ContainerHealth::Unhealthy),
+            ..test_container()
+        };
+
+        assert_eq!(state_color(&container, 0), COLOR_RED);
+    }
+
+    #[test]
+    fn state_color_exited_is_red() {
+        let container = Container {
+            state: ContainerState::Exited,
+            ..test_container()
+        };
+
+        assert_eq!(state_color(&container, 0), COLOR_RED);
+    }
+
+    #[test]
+    fn state_color_running_is_green() {
+        assert_eq!(state_color(&test_container(), 0), COLOR_GREEN);
+    }
+
+    #[test]
+    fn state_color_other_falls_back_to_default() {
+        let container = Container {
+            state: ContainerState::Other,
+            ..test_container()
+        };
+
+        assert_eq!(state_color(&container, 0x123456), 0x123456);
+    }
+
+    #[test]
+    fn state_field_includes_exit_code() {
+        let container = Container {
+            state: ContainerState::Exited,
+            exit_code: Some(137),
+            ..test_container()
+        };
+
+        assert_eq!(state_field(&container), "Exited (137)");
+    }
+
+    #[test]
+    fn state_field_other_falls_back_to_status() {
+        let container = Container {
+            state: ContainerState::Other,
+            status: "Created".to_string(),
+            ..test_container()
+        };
+
+        assert_eq!(state_field(&container), "Created");
+    }
+
+    #[test]
+    fn uptime_field_is_placeholder_when_not_running() {
+        let container = Container {
+            state: ContainerState::Exited,
+            started_at: Some(Utc::now()),
+            ..test_container()
+        };
+
+        assert_eq!(uptime_field(&container), "-");
+    }
+
+    #[test]
+    fn uptime_field_formats_minutes() {
+        let container = Container {
+            started_at: Some(Utc::now() - chrono::Duration::minutes(5)),
+            ..test_container()
+        };
+
+        assert_eq!(uptime_field(&container), "5m");
+    }
+
+    #[test]
+    fn field_value_placeholders_empty_and_truncates_long_values() {
+        assert_eq!(field_value(""), "-");
+        assert_eq!(field_value("serve"), "serve");
+
+        let long = "a".repeat(2000);
+        assert_eq!(field_value(&long).chars().count(), 1024);
+    }
+
+    #[test]
+    fn tail_chars_respects_char_boundaries() {
+        let s = "héllo wörld";
+
+        assert_eq!(tail_chars(s, 5), "wörld");
+        assert_eq!(tail_chars(s, 100), s);
+    }
+}
@@ -1,11 +1,39 @@
 use anyhow::Context;
 use bollard::{
-    container::{ListContainersOptions, LogOutput, LogsOptions},
+    container::{InspectContainerOptions, ListContainersOptions, LogOutput, LogsOptions},
+    errors::Error as DockerError,
+    models::ContainerInspectResponse,
+    system::EventsOptions,
     Docker,
 };
+use chrono::{DateTime, Utc};
 use futures::StreamExt;
 use itertools::Itertools;
 use log::{info, warn};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+/// Minimum time between forwarded events for the same container, so a
+/// flapping container doesn't trigger a re-render per individual event.
+/// `die` and `destroy` events are always forwarded.
+const EVENT_DEBOUNCE: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContainerEventKind {
+    Start,
+    Stop,
+    Die,
+    Destroy,
+    Health,
+}
+
+#[derive(Debug, Clone)]
+pub struct ContainerEvent {
+    pub kind: ContainerEventKind,
+    pub id: String,
+    pub name: String,
+}
 
 pub struct Container {
     pub id: String,
@@ -13,11 +41,87 @@ pub struct Container {
     pub image: String,
     pub command: String,
     pub status: String,
+    pub state: ContainerState,
+    pub health: Option<ContainerHealth>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub exit_code: Option<i64>,
+    /// `discord-status.name` override, falling back to the container name.
+    pub display_name: String,
+    /// `discord-status.tail` override for how many log lines to show.
+    pub log_tail_override: Option<u64>,
+    /// `discord-status.hidden` opt-out.
+    pub hidden: bool,
+    /// Raw Docker labels, so callers that can't go through `containers`'s
+    /// server-side label filter (e.g. inspect-based lookups) can still check
+    /// a container against `ContainerFilter::label_allowed`.
+    pub labels: HashMap<String, String>,
 }
 
-pub async fn logs(docker: &Docker, id: &str) -> Vec<LogOutput> {
+const LABEL_NAME: &str = "discord-status.name";
+const LABEL_TAIL: &str = "discord-status.tail";
+const LABEL_HIDDEN: &str = "discord-status.hidden";
+
+/// Per-container rendering overrides parsed from compose labels, so users
+/// can curate exactly what appears in Discord via their compose files.
+struct ContainerOverrides {
+    display_name: String,
+    log_tail_override: Option<u64>,
+    hidden: bool,
+}
+
+fn container_overrides(name: &str, labels: &HashMap<String, String>) -> ContainerOverrides {
+    ContainerOverrides {
+        display_name: labels
+            .get(LABEL_NAME)
+            .cloned()
+            .unwrap_or_else(|| name.to_string()),
+        log_tail_override: labels.get(LABEL_TAIL).and_then(|tail| tail.parse().ok()),
+        hidden: labels
+            .get(LABEL_HIDDEN)
+            .is_some_and(|hidden| hidden == "true"),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContainerState {
+    Running,
+    Exited,
+    Paused,
+    Restarting,
+    Other,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContainerHealth {
+    Healthy,
+    Unhealthy,
+    Starting,
+}
+
+fn parse_state(state: &str) -> ContainerState {
+    match state {
+        "running" => ContainerState::Running,
+        "exited" => ContainerState::Exited,
+        "paused" => ContainerState::Paused,
+        "restarting" => ContainerState::Restarting,
+        _ => ContainerState::Other,
+    }
+}
+
+fn parse_health(health: &str) -> Option<ContainerHealth> {
+    match health {
+        "healthy" => Some(ContainerHealth::Healthy),
+        "unhealthy" => Some(ContainerHealth::Unhealthy),
+        "starting" => Some(ContainerHealth::Starting),
+        _ => None,
+    }
+}
+
+pub async fn logs(docker: &Docker, id: &str, tail_lines: u64) -> Vec<LogOutput> {
     info!("Getting logs for container: {}", id);
 
+    let tail = tail_lines.to_string();
+
     let logs = docker
         .logs::<&str>(
             id,
@@ -28,7 +132,7 @@ pub async fn logs(docker: &Docker, id: &str) -> Vec<LogOutput> {
                 since: 0,
                 until: 0,
                 timestamps: false,
-                tail: "40",
+                tail: &tail,
             }),
         )
         .collect::<Vec<_>>()
@@ -42,13 +146,102 @@ pub async fn logs(docker: &Docker, id: &str) -> Vec<LogOutput> {
     logs
 }
 
-pub async fn containers(docker: &Docker) -> anyhow::Result<Vec<Container>> {
+/// Spawns a long-lived task that consumes `docker.events()` and forwards
+/// typed container lifecycle events over the returned channel. This lets
+/// `run` react to individual changes instead of re-listing every container
+/// on a fixed interval.
+pub fn watch_events(docker: Docker) -> mpsc::Receiver<ContainerEvent> {
+    let (tx, rx) = mpsc::channel(64);
+
+    tokio::spawn(async move {
+        let mut filters = HashMap::new();
+        filters.insert("type".to_string(), vec!["container".to_string()]);
+
+        let mut stream = docker.events(Some(EventsOptions::<String> {
+            since: None,
+            until: None,
+            filters,
+        }));
+
+        let mut last_forwarded: HashMap<String, Instant> = HashMap::new();
+
+        while let Some(message) = stream.next().await {
+            let message = match message {
+                Ok(message) => message,
+                Err(e) => {
+                    warn!("Error reading docker event: {:?}", e);
+                    continue;
+                }
+            };
+
+            let Some(kind) = message.action.as_deref().and_then(event_kind) else {
+                continue;
+            };
+
+            let Some(actor) = message.actor else {
+                continue;
+            };
+            let Some(id) = actor.id else {
+                continue;
+            };
+            let name = actor
+                .attributes
+                .as_ref()
+                .and_then(|attrs| attrs.get("name"))
+                .cloned()
+                .unwrap_or_else(|| id.clone());
+
+            // Terminal events are never debounced: a `docker rm -f` emits
+            // `die` then `destroy` back-to-back, and swallowing either one
+            // leaves a channel orphaned until the next reconcile.
+            let debounced = !matches!(kind, ContainerEventKind::Die | ContainerEventKind::Destroy);
+
+            if debounced {
+                if let Some(last) = last_forwarded.get(&id) {
+                    if last.elapsed() < EVENT_DEBOUNCE {
+                        continue;
+                    }
+                }
+                last_forwarded.insert(id.clone(), Instant::now());
+            }
+
+            if tx.send(ContainerEvent { kind, id, name }).await.is_err() {
+                break;
+            }
+        }
+
+        warn!("Docker event stream ended");
+    });
+
+    rx
+}
+
+fn event_kind(action: &str) -> Option<ContainerEventKind> {
+    match action {
+        "start" => Some(ContainerEventKind::Start),
+        "stop" => Some(ContainerEventKind::Stop),
+        "die" => Some(ContainerEventKind::Die),
+        "destroy" => Some(ContainerEventKind::Destroy),
+        _ if action.starts_with("health_status") => Some(ContainerEventKind::Health),
+        _ => None,
+    }
+}
+
+pub async fn containers(
+    docker: &Docker,
+    label_selector: Option<&str>,
+) -> anyhow::Result<Vec<Container>> {
+    let mut filters: HashMap<String, Vec<String>> = HashMap::new();
+    if let Some(selector) = label_selector {
+        filters.insert("label".to_string(), vec![selector.to_string()]);
+    }
+
     let cs = docker
-        .list_containers::<&str>(Some(ListContainersOptions {
+        .list_containers::<String>(Some(ListContainersOptions {
             all: true,
             limit: None,
             size: false,
-            filters: Default::default(),
+            filters,
         }))
         .await
         .context("Failed to list containers")?;
@@ -71,15 +264,191 @@ pub async fn containers(docker: &Docker) -> anyhow::Result<Vec<Container>> {
                 return None;
             };
 
+            let name = name.trim_start_matches('/').to_string();
+            let labels = c.labels.clone().unwrap_or_default();
+            let overrides = container_overrides(&name, &labels);
+
             Some(Container {
                 id: id.to_string(),
-                name: name.to_string(),
+                name,
                 image: image.to_string(),
                 command: command.to_string(),
                 status: status.to_string(),
+                state: c
+                    .state
+                    .as_deref()
+                    .map(parse_state)
+                    .unwrap_or(ContainerState::Other),
+                health: None,
+                started_at: None,
+                exit_code: None,
+                display_name: overrides.display_name,
+                log_tail_override: overrides.log_tail_override,
+                hidden: overrides.hidden,
+                labels,
             })
         })
         .collect_vec();
 
     Ok(containers)
 }
+
+/// Looks up a single container by name, inspecting it directly for
+/// health/uptime/exit code instead of diffing the whole container list.
+pub async fn container_by_name(docker: &Docker, name: &str) -> anyhow::Result<Option<Container>> {
+    inspect(docker, name).await
+}
+
+/// Looks up a single container by id, for handling button interactions
+/// whose custom IDs are keyed off the container id rather than its name.
+pub async fn container_by_id(docker: &Docker, id: &str) -> anyhow::Result<Option<Container>> {
+    inspect(docker, id).await
+}
+
+/// Inspects a single container (by id or name) for the full detail a
+/// rendered embed needs: state, health, uptime and exit code.
+async fn inspect(docker: &Docker, id_or_name: &str) -> anyhow::Result<Option<Container>> {
+    let details = match docker
+        .inspect_container(id_or_name, None::<InspectContainerOptions>)
+        .await
+    {
+        Ok(details) => details,
+        Err(DockerError::DockerResponseServerError {
+            status_code: 404, ..
+        }) => return Ok(None),
+        Err(e) => return Err(e).context("Failed to inspect container"),
+    };
+
+    Ok(Some(container_from_inspect(details)))
+}
+
+fn container_from_inspect(details: ContainerInspectResponse) -> Container {
+    let id = details.id.unwrap_or_default();
+
+    let name = details
+        .name
+        .as_deref()
+        .unwrap_or(&id)
+        .trim_start_matches('/')
+        .to_string();
+
+    let image = details
+        .config
+        .as_ref()
+        .and_then(|config| config.image.clone())
+        .unwrap_or_default();
+
+    let command = details
+        .config
+        .as_ref()
+        .and_then(|config| config.cmd.as_ref())
+        .map(|cmd| cmd.join(" "))
+        .unwrap_or_default();
+
+    let state_details = details.state.unwrap_or_default();
+
+    let status = state_details
+        .status
+        .map(|status| status.to_string())
+        .unwrap_or_default();
+    let state = parse_state(&status);
+
+    let health = state_details
+        .health
+        .and_then(|health| health.status)
+        .and_then(|status| parse_health(&status.to_string()));
+
+    let started_at = state_details
+        .started_at
+        .as_deref()
+        .and_then(|started_at| DateTime::parse_from_rfc3339(started_at).ok())
+        .map(|started_at| started_at.with_timezone(&Utc));
+
+    let labels = details
+        .config
+        .as_ref()
+        .and_then(|config| config.labels.clone())
+        .unwrap_or_default();
+    let overrides = container_overrides(&name, &labels);
+
+    Container {
+        id,
+        name,
+        image,
+        command,
+        status,
+        state,
+        health,
+        started_at,
+        exit_code: state_details.exit_code,
+        display_name: overrides.display_name,
+        log_tail_override: overrides.log_tail_override,
+        hidden: overrides.hidden,
+        labels,
+    }
+}
+
+pub async fn restart_container(docker: &Docker, id: &str) -> anyhow::Result<()> {
+    info!("Restarting container: {}", id);
+
+    docker
+        .restart_container(id, None)
+        .await
+        .context("Failed to restart container")
+}
+
+pub async fn stop_container(docker: &Docker, id: &str) -> anyhow::Result<()> {
+    info!("Stopping container: {}", id);
+
+    docker
+        .stop_container(id, None)
+        .await
+        .context("Failed to stop container")
+}
+
+pub async fn start_container(docker: &Docker, id: &str) -> anyhow::Result<()> {
+    info!("Starting container: {}", id);
+
+    docker
+        .start_container::<&str>(id, None)
+        .await
+        .context("Failed to start container")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn container_overrides_falls_back_to_container_name() {
+        let overrides = container_overrides("web", &HashMap::new());
+
+        assert_eq!(overrides.display_name, "web");
+        assert_eq!(overrides.log_tail_override, None);
+        assert!(!overrides.hidden);
+    }
+
+    #[test]
+    fn container_overrides_parses_labels() {
+        let mut labels = HashMap::new();
+        labels.insert(LABEL_NAME.to_string(), "Web Server".to_string());
+        labels.insert(LABEL_TAIL.to_string(), "100".to_string());
+        labels.insert(LABEL_HIDDEN.to_string(), "true".to_string());
+
+        let overrides = container_overrides("web", &labels);
+
+        assert_eq!(overrides.display_name, "Web Server");
+        assert_eq!(overrides.log_tail_override, Some(100));
+        assert!(overrides.hidden);
+    }
+
+    #[test]
+    fn container_overrides_ignores_unparseable_tail() {
+        let mut labels = HashMap::new();
+        labels.insert(LABEL_TAIL.to_string(), "not-a-number".to_string());
+
+        let overrides = container_overrides("web", &labels);
+
+        assert_eq!(overrides.log_tail_override, None);
+    }
+}
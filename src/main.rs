@@ -4,17 +4,22 @@ use anyhow::Context;
 use bollard::Docker;
 use log::{error, info, warn};
 use std::collections::HashMap;
-use twilight_http::Client as HttpClient;
-use twilight_model::id::{
-    marker::{ChannelMarker, GuildMarker, MessageMarker},
-    Id,
+use twilight_http::{error::ErrorType as DiscordErrorType, Client as HttpClient};
+use twilight_model::{
+    application::interaction::Interaction,
+    id::{
+        marker::{ChannelMarker, GuildMarker, MessageMarker},
+        Id,
+    },
 };
 
+mod config;
 mod discord;
 mod docker;
+mod store;
 
-const GUILD: Id<GuildMarker> = Id::new(1209473653759016990);
-const CATEGORY: Id<ChannelMarker> = Id::new(1218191011348615240);
+use config::Settings;
+use store::Store;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -26,32 +31,320 @@ async fn main() -> anyhow::Result<()> {
     )
     .unwrap();
 
+    let settings = Settings::load().context("Failed to load settings")?;
+
     let token = std::env::var("DISCORD_TOKEN").context("Failed to get DISCORD_TOKEN")?;
-    let discord = HttpClient::new(token);
+    let discord = HttpClient::new(token.clone());
 
     let docker = Docker::connect_with_socket_defaults().context("Failed to connect to docker")?;
 
-    let mut message_cache = Cache::new();
+    let store = Store::open(&settings.cache_path).context("Failed to open cache store")?;
+    let mut message_cache = store.load_all().context("Failed to load cache")?;
+
+    // Manual deletions in Discord leave a persisted entry with no backing
+    // channel behind; drop those before we start creating/updating anything.
+    prune_stale_channels(&discord, &store, &mut message_cache)
+        .await
+        .unwrap_or_else(|e| error!("Failed to prune stale channels: {:?}", e));
+
+    // Establish a known-good state before reacting to individual events.
+    reconcile(&discord, &docker, &settings, &store, &mut message_cache)
+        .await
+        .unwrap_or_else(|e| error!("Thread error: {:?}", e));
+
+    let mut events = docker::watch_events(docker.clone());
+    let mut interactions = discord::watch_interactions(token);
+    let mut reconcile_interval =
+        tokio::time::interval(std::time::Duration::from_secs(settings.poll_interval_secs));
+    reconcile_interval.tick().await; // first tick fires immediately, we just reconciled above
 
     loop {
-        run(&discord, &docker, &mut message_cache)
-            .await
-            .unwrap_or_else(|e| error!("Thread error: {:?}", e));
+        tokio::select! {
+            event = events.recv() => {
+                let Some(event) = event else {
+                    error!("Docker event stream closed, falling back to reconciliation only");
+                    reconcile_interval.tick().await;
+                    reconcile(&discord, &docker, &settings, &store, &mut message_cache)
+                        .await
+                        .unwrap_or_else(|e| error!("Thread error: {:?}", e));
+                    continue;
+                };
+
+                handle_event(&discord, &docker, &settings, &store, &mut message_cache, event)
+                    .await
+                    .unwrap_or_else(|e| error!("Event handling error: {:?}", e));
+            }
+            interaction = interactions.recv() => {
+                let Some(interaction) = interaction else {
+                    error!("Gateway connection closed, button presses will stop working");
+                    continue;
+                };
 
-        tokio::time::sleep(std::time::Duration::from_secs(30)).await;
+                handle_interaction(&discord, &docker, &settings, &message_cache, interaction)
+                    .await
+                    .unwrap_or_else(|e| error!("Interaction handling error: {:?}", e));
+            }
+            _ = reconcile_interval.tick() => {
+                reconcile(&discord, &docker, &settings, &store, &mut message_cache)
+                    .await
+                    .unwrap_or_else(|e| error!("Thread error: {:?}", e));
+            }
+        }
     }
 }
 
 type Cache = HashMap<String, (Id<ChannelMarker>, Id<MessageMarker>)>;
 
-async fn run(
+/// Whether a `twilight_http` error is Discord reporting the channel itself
+/// is gone (404), as opposed to a transient failure that says nothing about
+/// whether the channel still exists.
+fn is_unknown_channel(error: &twilight_http::Error) -> bool {
+    matches!(error.kind(), DiscordErrorType::Response { status, .. } if status.get() == 404)
+}
+
+/// Verifies each persisted channel still exists via the Discord API and
+/// drops stale entries, so manual deletions in Discord are handled
+/// gracefully instead of leaving orphaned cache entries forever.
+async fn prune_stale_channels(
+    discord: &HttpClient,
+    store: &Store,
+    message_cache: &mut Cache,
+) -> anyhow::Result<()> {
+    let mut stale = vec![];
+    for (name, &(channel_id, _)) in message_cache.iter() {
+        match discord.channel(channel_id).await {
+            Ok(_) => {}
+            Err(e) if is_unknown_channel(&e) => {
+                warn!(
+                    "Persisted channel (discord id: {}) for container {} no longer exists, dropping",
+                    channel_id, name
+                );
+                stale.push(name.clone());
+            }
+            // A transient error (rate limit, network blip) doesn't mean the
+            // channel is gone; leave the entry alone rather than dropping it
+            // and having `reconcile` recreate it.
+            Err(e) => {
+                warn!(
+                    "Failed to check persisted channel (discord id: {}) for container {}, leaving cached: {:?}",
+                    channel_id, name, e
+                );
+            }
+        }
+    }
+
+    for name in stale {
+        message_cache.remove(&name);
+        store.remove(&name)?;
+    }
+
+    anyhow::Ok(())
+}
+
+/// Reacts to a single container lifecycle event instead of re-listing and
+/// re-rendering every container.
+async fn handle_event(
     discord: &HttpClient,
     docker: &Docker,
+    settings: &Settings,
+    store: &Store,
     message_cache: &mut Cache,
+    event: docker::ContainerEvent,
 ) -> anyhow::Result<()> {
-    let containers = docker::containers(docker)
+    use docker::ContainerEventKind::*;
+
+    if !settings.container_allowed(&event.name) {
+        return anyhow::Ok(());
+    }
+
+    match event.kind {
+        Destroy => {
+            if let Some((channel_id, _)) = message_cache.remove(&event.name) {
+                info!(
+                    "Removing channel (discord id: {}) for container: {}",
+                    channel_id, event.name
+                );
+
+                store.remove(&event.name)?;
+
+                discord
+                    .delete_channel(channel_id)
+                    .await
+                    .context("Failed to delete channel")?;
+            }
+        }
+        Start if !message_cache.contains_key(&event.name) => {
+            let Some(container) = docker::container_by_id(docker, &event.id).await? else {
+                return anyhow::Ok(());
+            };
+            if container.hidden || !settings.containers.label_allowed(&container.labels) {
+                return anyhow::Ok(());
+            }
+
+            info!("Creating channel for container: {}", event.name);
+
+            let channel = discord
+                .create_guild_channel(settings.guild_id, &container.display_name)
+                .context("Failed to set up channel")?
+                .parent_id(settings.category_id)
+                .await
+                .context("Failed to create channel")?
+                .model()
+                .await
+                .context("Failed to get channel model")?;
+
+            let message = discord
+                .create_message(channel.id)
+                .content("> Content goes here...")
+                .context("Failed to set up message")?
+                .await
+                .context("Failed to create message")?
+                .model()
+                .await
+                .context("Failed to get message model")?;
+
+            message_cache.insert(event.name.clone(), (channel.id, message.id));
+            store.insert(&event.name, channel.id, message.id)?;
+
+            render_container(discord, docker, settings, message_cache, &event.name).await?;
+        }
+        Start | Stop | Die | Health => {
+            render_container(discord, docker, settings, message_cache, &event.name).await?;
+        }
+    }
+
+    anyhow::Ok(())
+}
+
+/// Handles a button press on a container's message: runs the requested
+/// docker action (gated behind `settings.allowed_roles` for destructive
+/// ones), acks the interaction ephemerally, then re-renders the container's
+/// embed.
+async fn handle_interaction(
+    discord: &HttpClient,
+    docker: &Docker,
+    settings: &Settings,
+    message_cache: &Cache,
+    interaction: Interaction,
+) -> anyhow::Result<()> {
+    let Some((action, container_id)) = discord::interaction_action(&interaction) else {
+        return anyhow::Ok(());
+    };
+    let container_id = container_id.to_string();
+
+    let client = discord.interaction(interaction.application_id);
+
+    let destructive = matches!(action, "restart" | "stop" | "start");
+    if destructive && !discord::member_has_role(&interaction, &settings.allowed_roles) {
+        client
+            .create_response(
+                interaction.id,
+                &interaction.token,
+                &discord::ephemeral_response("You don't have permission to do that."),
+            )
+            .await
+            .context("Failed to respond to interaction")?;
+
+        return anyhow::Ok(());
+    }
+
+    // `restart`/`stop` can take well past Discord's 3-second interaction
+    // deadline (Docker's default stop grace period alone is ~10s), so defer
+    // first and fill in the real result afterwards rather than racing it.
+    client
+        .create_response(
+            interaction.id,
+            &interaction.token,
+            &discord::deferred_response(),
+        )
+        .await
+        .context("Failed to defer interaction")?;
+
+    let result = match action {
+        "restart" => docker::restart_container(docker, &container_id).await,
+        "stop" => docker::stop_container(docker, &container_id).await,
+        "start" => docker::start_container(docker, &container_id).await,
+        "refresh" => Ok(()),
+        _ => return anyhow::Ok(()),
+    };
+
+    let ack = match &result {
+        Ok(()) => format!("Done: {}", action),
+        Err(e) => format!("Failed to {}: {:?}", action, e),
+    };
+
+    client
+        .update_response(&interaction.token)
+        .content(Some(&ack))
+        .context("Failed to set up interaction response update")?
+        .await
+        .context("Failed to update interaction response")?;
+
+    if let Some(container) = docker::container_by_id(docker, &container_id).await? {
+        render_container(discord, docker, settings, message_cache, &container.name).await?;
+    }
+
+    anyhow::Ok(())
+}
+
+/// Re-renders the embed for a single, already-known container.
+async fn render_container(
+    discord: &HttpClient,
+    docker: &Docker,
+    settings: &Settings,
+    message_cache: &Cache,
+    container_name: &str,
+) -> anyhow::Result<()> {
+    let Some(&(channel_id, message_id)) = message_cache.get(container_name) else {
+        return anyhow::Ok(());
+    };
+
+    let Some(container) = docker::container_by_name(docker, container_name).await? else {
+        return anyhow::Ok(());
+    };
+
+    info!("Updating message for container: {}", container_name);
+
+    let tail_lines = container.log_tail_override.unwrap_or(settings.log_tail_lines);
+    let logs = docker::logs(docker, &container.id, tail_lines).await;
+
+    let embed = discord::embed_container(&container, &logs, settings.embed_color)
+        .context("Failed to create embed")?;
+    let components = discord::container_components(&container);
+
+    discord
+        .update_message(channel_id, message_id)
+        .content(None)
+        .context("Failed to set up message update")?
+        .embeds(Some(&[embed]))
+        .context("Failed to set up message update")?
+        .components(Some(&components))
+        .context("Failed to set up message update")?
         .await
-        .context("Failed to get containers")?;
+        .context("Failed to update message")?
+        .model()
+        .await
+        .context("Failed to get message model")?;
+
+    anyhow::Ok(())
+}
+
+/// Full reconciliation pass: diffs the complete container list against the
+/// cache. Used to establish the initial state and as a periodic fallback in
+/// case individual docker events are missed.
+async fn reconcile(
+    discord: &HttpClient,
+    docker: &Docker,
+    settings: &Settings,
+    store: &Store,
+    message_cache: &mut Cache,
+) -> anyhow::Result<()> {
+    let containers = docker::containers(docker, settings.containers.label_selector.as_deref())
+        .await
+        .context("Failed to get containers")?
+        .into_iter()
+        .filter(|c| !c.hidden && settings.container_allowed(&c.name))
+        .collect::<Vec<_>>();
 
     info!(
         "Got containers {:?}",
@@ -76,9 +369,10 @@ async fn run(
 
         to_remove.push(name.clone());
     }
-    to_remove.into_iter().for_each(|name| {
+    for name in to_remove {
         message_cache.remove(&name);
-    });
+        store.remove(&name)?;
+    }
 
     // create channels for containers that don't have one
     let mut to_add = vec![];
@@ -89,9 +383,9 @@ async fn run(
         info!("Creating channel for container: {}", container.name);
 
         let channel = discord
-            .create_guild_channel(GUILD, &container.name)
+            .create_guild_channel(settings.guild_id, &container.display_name)
             .context("Failed to set up channel")?
-            .parent_id(CATEGORY)
+            .parent_id(settings.category_id)
             .await
             .context("Failed to create channel")?
             .model()
@@ -110,34 +404,14 @@ async fn run(
 
         to_add.push((container.name.clone(), (channel.id, message.id)));
     }
-    to_add.into_iter().for_each(|(name, (channel, message))| {
+    for (name, (channel, message)) in to_add {
+        store.insert(&name, channel, message)?;
         message_cache.insert(name, (channel, message));
-    });
+    }
 
     // update messages in channels
-    for (container_name, (channel_id, message_id)) in message_cache.iter() {
-        info!("Updating message for container: {}", container_name);
-
-        let container = containers
-            .iter()
-            .find(|c| &c.name == container_name)
-            .context("Failed to find container")?;
-
-        let logs = docker::logs(docker, &container.id).await;
-
-        let embed = discord::embed_container(container, &logs).context("Failed to create embed")?;
-
-        discord
-            .update_message(*channel_id, *message_id)
-            .content(None)
-            .context("Failed to set up message update")?
-            .embeds(Some(&[embed]))
-            .context("Failed to set up message update")?
-            .await
-            .context("Failed to update message")?
-            .model()
-            .await
-            .context("Failed to get message model")?;
+    for container_name in message_cache.keys().cloned().collect::<Vec<_>>() {
+        render_container(discord, docker, settings, message_cache, &container_name).await?;
     }
 
     anyhow::Ok(())
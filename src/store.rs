@@ -0,0 +1,101 @@
+use anyhow::Context;
+use std::collections::HashMap;
+use twilight_model::id::{
+    marker::{ChannelMarker, MessageMarker},
+    Id,
+};
+
+/// Persists the `container_name -> (ChannelId, MessageId)` cache to disk so
+/// a restart doesn't forget which channels already exist and start creating
+/// duplicates.
+pub struct Store {
+    db: sled::Db,
+}
+
+impl Store {
+    pub fn open(path: &str) -> anyhow::Result<Self> {
+        let db = sled::open(path).context("Failed to open cache database")?;
+
+        Ok(Self { db })
+    }
+
+    /// Loads the full persisted cache into memory. Called once at startup.
+    pub fn load_all(&self) -> anyhow::Result<HashMap<String, (Id<ChannelMarker>, Id<MessageMarker>)>> {
+        let mut cache = HashMap::new();
+
+        for entry in self.db.iter() {
+            let (key, value) = entry.context("Failed to read cache entry")?;
+
+            let name = String::from_utf8(key.to_vec()).context("Failed to decode cache key")?;
+            let (channel_id, message_id) =
+                decode_ids(&value).context("Failed to decode cache value")?;
+
+            cache.insert(name, (channel_id, message_id));
+        }
+
+        Ok(cache)
+    }
+
+    /// Writes a single mapping through to disk.
+    pub fn insert(
+        &self,
+        name: &str,
+        channel_id: Id<ChannelMarker>,
+        message_id: Id<MessageMarker>,
+    ) -> anyhow::Result<()> {
+        self.db
+            .insert(name, &encode_ids(channel_id, message_id))
+            .context("Failed to persist cache entry")?;
+        self.db.flush().context("Failed to flush cache")?;
+
+        Ok(())
+    }
+
+    /// Removes a single mapping from disk.
+    pub fn remove(&self, name: &str) -> anyhow::Result<()> {
+        self.db
+            .remove(name)
+            .context("Failed to remove cache entry")?;
+        self.db.flush().context("Failed to flush cache")?;
+
+        Ok(())
+    }
+}
+
+fn encode_ids(channel_id: Id<ChannelMarker>, message_id: Id<MessageMarker>) -> [u8; 16] {
+    let mut value = [0u8; 16];
+    value[..8].copy_from_slice(&channel_id.get().to_be_bytes());
+    value[8..].copy_from_slice(&message_id.get().to_be_bytes());
+    value
+}
+
+fn decode_ids(value: &[u8]) -> anyhow::Result<(Id<ChannelMarker>, Id<MessageMarker>)> {
+    anyhow::ensure!(value.len() == 16, "cache value has unexpected length");
+
+    let channel_id = u64::from_be_bytes(value[..8].try_into().unwrap());
+    let message_id = u64::from_be_bytes(value[8..].try_into().unwrap());
+
+    Ok((Id::new(channel_id), Id::new(message_id)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_decode_round_trips() {
+        let channel_id = Id::new(123456789);
+        let message_id = Id::new(987654321);
+
+        let encoded = encode_ids(channel_id, message_id);
+        let (decoded_channel, decoded_message) = decode_ids(&encoded).unwrap();
+
+        assert_eq!(decoded_channel, channel_id);
+        assert_eq!(decoded_message, message_id);
+    }
+
+    #[test]
+    fn decode_rejects_wrong_length() {
+        assert!(decode_ids(&[0u8; 8]).is_err());
+    }
+}